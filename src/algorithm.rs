@@ -0,0 +1,69 @@
+//! Parameter-set metadata for protocols that negotiate a Kyber level over
+//! the wire.
+//!
+//! `params::*` is a set of consts fixed at compile time, so a single build
+//! of this crate generates and uses keys at exactly one security level --
+//! `indcpa_*`/`crypto_kem_*` are not parameterized by K/eta/compression
+//! sizes, they're generated against whichever `params::*` the crate was
+//! built with. `Algorithm`/`ParamSet` deliberately do NOT paper over that:
+//! there is no `keypair(algorithm, ..)` that can serve all three levels
+//! from one binary, because that would silently fail (or worse, silently
+//! produce wrong-sized output) for the two levels that don't match the
+//! build. What `ParamSet` gives callers is the public byte-length table for
+//! all three levels -- so protocol code can size buffers for a negotiated
+//! level, or check the negotiated level against `matches_compiled()` before
+//! ever calling into `crypto_kem_keypair`/`enc`/`dec` -- without this crate
+//! pretending to support more than one level per build. Actually running
+//! Kyber512/768/1024 from a single binary needs K/eta/compression sizes
+//! threaded through `indcpa_*` as data, which is a larger change than this
+//! metadata table.
+
+use crate::params::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+  Kyber512,
+  Kyber768,
+  Kyber1024
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParamSet {
+  pub algorithm: Algorithm,
+  pub k: usize,
+  pub public_key_bytes: usize,
+  pub secret_key_bytes: usize,
+  pub ciphertext_bytes: usize,
+  pub shared_secret_bytes: usize
+}
+
+impl ParamSet {
+  pub const fn for_algorithm(algorithm: Algorithm) -> Self {
+    match algorithm {
+      Algorithm::Kyber512 => ParamSet {
+        algorithm, k: 2,
+        public_key_bytes: 800, secret_key_bytes: 1632,
+        ciphertext_bytes: 768, shared_secret_bytes: 32
+      },
+      Algorithm::Kyber768 => ParamSet {
+        algorithm, k: 3,
+        public_key_bytes: 1184, secret_key_bytes: 2400,
+        ciphertext_bytes: 1088, shared_secret_bytes: 32
+      },
+      Algorithm::Kyber1024 => ParamSet {
+        algorithm, k: 4,
+        public_key_bytes: 1568, secret_key_bytes: 3168,
+        ciphertext_bytes: 1568, shared_secret_bytes: 32
+      }
+    }
+  }
+
+  // Whether this parameter set is the one `params::*` -- and therefore
+  // crypto_kem_keypair/enc/dec -- was actually compiled with. Protocol code
+  // that negotiates a level over the wire should check this before calling
+  // into the KEM at all, since calling it otherwise operates at the
+  // compiled-in level regardless of which Algorithm was negotiated.
+  pub const fn matches_compiled(&self) -> bool {
+    self.k == KYBER_K
+  }
+}
@@ -0,0 +1,205 @@
+//! Deterministic AES-256-CTR_DRBG matching the NIST reference RNG used by
+//! the SUPERCOP KAT generator (the same `rng.c` every PQC submission's
+//! `.rsp` files were produced against).
+//!
+//! `crypto_kem_keypair`/`crypto_kem_enc` already accept a `seed: Option<...>`
+//! escape hatch for reproducing KATs, but that requires the caller to
+//! hand-feed the exact deterministic bytes the reference implementation
+//! would have drawn from its RNG -- which is exactly the kind of detail
+//! that silently diverges between a Rust peer and a C peer and is painful
+//! to track down. `CtrDrbg` implements `RngCore`/`CryptoRng` so it can be
+//! passed directly as the `rng` argument instead: driving key generation
+//! through it from the same 48-byte seed as the KAT vectors produces
+//! bit-identical keys, ciphertexts and shared secrets without touching the
+//! `seed` tuple at all.
+
+use aes::Aes256;
+use aes::cipher::{BlockEncrypt, KeyInit, generic_array::GenericArray};
+use rand_core::{RngCore, CryptoRng, Error};
+
+const AES256_KEYBYTES: usize = 32;
+const AES_BLOCKBYTES: usize = 16;
+
+/// NIST SP 800-90A AES-256 CTR_DRBG, without derivation function, as used by
+/// the SUPERCOP/PQC KAT request generators.
+pub struct CtrDrbg {
+  key: [u8; AES256_KEYBYTES],
+  v: [u8; AES_BLOCKBYTES]
+}
+
+impl CtrDrbg {
+  /// Instantiates the DRBG from a 48-byte seed (the same seed the KAT
+  /// generator calls `randombytes_init` with): key and V start as all
+  /// zeroes, then one CTR_DRBG_Update is applied with the seed as provided
+  /// data, per NIST SP 800-90A 10.2.1.3.1.
+  pub fn new(seed: &[u8; 48]) -> Self {
+    let mut drbg = CtrDrbg { key: [0u8; AES256_KEYBYTES], v: [0u8; AES_BLOCKBYTES] };
+    drbg.update(Some(seed));
+    drbg
+  }
+
+  // CTR_DRBG_Update: encrypts an incrementing V under the current key to
+  // build a 48-byte temp buffer, XORs in provided_data (if any), and splits
+  // the result back into (key, V).
+  fn update(&mut self, provided_data: Option<&[u8; 48]>) {
+    let mut temp = [0u8; AES256_KEYBYTES + AES_BLOCKBYTES];
+    let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+
+    for chunk in temp.chunks_mut(AES_BLOCKBYTES) {
+      increment_counter(&mut self.v);
+      let mut block = GenericArray::clone_from_slice(&self.v);
+      cipher.encrypt_block(&mut block);
+      chunk.copy_from_slice(&block);
+    }
+
+    if let Some(data) = provided_data {
+      for i in 0..temp.len() {
+        temp[i] ^= data[i];
+      }
+    }
+
+    self.key.copy_from_slice(&temp[..AES256_KEYBYTES]);
+    self.v.copy_from_slice(&temp[AES256_KEYBYTES..]);
+  }
+
+  // Fills `out` with the next `out.len()` bytes of DRBG output, then runs
+  // CTR_DRBG_Update (no additional input) as required after every request.
+  fn generate(&mut self, out: &mut [u8]) {
+    let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+
+    for chunk in out.chunks_mut(AES_BLOCKBYTES) {
+      increment_counter(&mut self.v);
+      let mut block = GenericArray::clone_from_slice(&self.v);
+      cipher.encrypt_block(&mut block);
+      chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+
+    self.update(None);
+  }
+}
+
+fn increment_counter(v: &mut [u8; AES_BLOCKBYTES]) {
+  for byte in v.iter_mut().rev() {
+    *byte = byte.wrapping_add(1);
+    if *byte != 0 {
+      break;
+    }
+  }
+}
+
+impl RngCore for CtrDrbg {
+  fn next_u32(&mut self) -> u32 {
+    let mut buf = [0u8; 4];
+    self.generate(&mut buf);
+    u32::from_le_bytes(buf)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let mut buf = [0u8; 8];
+    self.generate(&mut buf);
+    u64::from_le_bytes(buf)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    self.generate(dest);
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.generate(dest);
+    Ok(())
+  }
+}
+
+impl CryptoRng for CtrDrbg {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Seed = {0, 1, .., 47}, the canonical seed NIST PQC KAT generators
+  // (`randombytes_init`) are driven with. Expected output is the first 32
+  // bytes of the AES-256 CTR_DRBG (no derivation function) keystream for
+  // that seed, per NIST SP 800-90A 10.2.1 -- i.e. what a KAT's `.rsp` file
+  // assumes `randombytes` returns before any Kyber-specific processing.
+  const KAT_SEED: [u8; 48] = {
+    let mut seed = [0u8; 48];
+    let mut i = 0;
+    while i < 48 {
+      seed[i] = i as u8;
+      i += 1;
+    }
+    seed
+  };
+  const KAT_FIRST32: [u8; 32] = [
+    0x06, 0x15, 0x50, 0x23, 0x4d, 0x15, 0x8c, 0x5e, 0xc9, 0x55, 0x95, 0xfe, 0x04, 0xef, 0x7a, 0x25,
+    0x76, 0x7f, 0x2e, 0x24, 0xcc, 0x2b, 0xc4, 0x79, 0xd0, 0x9d, 0x86, 0xdc, 0x9a, 0xbc, 0xfd, 0xe7
+  ];
+
+  #[test]
+  fn matches_kat_vector_through_fill_bytes() {
+    let mut drbg = CtrDrbg::new(&KAT_SEED);
+    let mut out = [0u8; 32];
+    drbg.fill_bytes(&mut out);
+    assert_eq!(out, KAT_FIRST32);
+  }
+
+  #[test]
+  fn same_seed_round_trips_to_same_stream() {
+    let mut a = CtrDrbg::new(&KAT_SEED);
+    let mut b = CtrDrbg::new(&KAT_SEED);
+    let mut out_a = [0u8; 64];
+    let mut out_b = [0u8; 64];
+    a.fill_bytes(&mut out_a);
+    b.fill_bytes(&mut out_b);
+    assert_eq!(out_a, out_b);
+  }
+
+  #[test]
+  fn different_seed_diverges() {
+    let mut seed2 = KAT_SEED;
+    seed2[47] ^= 1;
+    let mut a = CtrDrbg::new(&KAT_SEED);
+    let mut b = CtrDrbg::new(&seed2);
+    let mut out_a = [0u8; 32];
+    let mut out_b = [0u8; 32];
+    a.fill_bytes(&mut out_a);
+    b.fill_bytes(&mut out_b);
+    assert_ne!(out_a, out_b);
+  }
+
+  // Drives crypto_kem_keypair/enc/dec through the normal `rng` argument,
+  // seeded from CtrDrbg, instead of the seed-tuple backdoor. Two runs from
+  // the same 48-byte seed must produce bit-identical keys, ciphertext and
+  // shared secrets, and decapsulation must recover the same shared secret
+  // encapsulation produced -- all through the `rng` path, with the `seed`
+  // tuple left as None.
+  #[test]
+  fn deterministic_keypair_encaps_decaps_round_trip_through_rng() {
+    use crate::api::{crypto_kem_keypair, crypto_kem_enc, crypto_kem_dec};
+    use crate::params::*;
+
+    fn run(seed: &[u8; 48]) -> ([u8; KYBER_PUBLICKEYBYTES], [u8; KYBER_CIPHERTEXTBYTES], [u8; KYBER_SYMBYTES], [u8; KYBER_SYMBYTES]) {
+      let mut rng = CtrDrbg::new(seed);
+      let mut pk = [0u8; KYBER_PUBLICKEYBYTES];
+      let mut sk = [0u8; KYBER_SECRETKEYBYTES];
+      crypto_kem_keypair(&mut pk, &mut sk, &mut rng, None).unwrap();
+
+      let mut ct = [0u8; KYBER_CIPHERTEXTBYTES];
+      let mut ss_enc = [0u8; KYBER_SYMBYTES];
+      crypto_kem_enc(&mut ct, &mut ss_enc, &pk, &mut rng, None).unwrap();
+
+      let mut ss_dec = [0u8; KYBER_SYMBYTES];
+      crypto_kem_dec(&mut ss_dec, &ct, &sk).unwrap();
+
+      (pk, ct, ss_enc, ss_dec)
+    }
+
+    let (pk_a, ct_a, ss_enc_a, ss_dec_a) = run(&KAT_SEED);
+    let (pk_b, ct_b, ss_enc_b, ss_dec_b) = run(&KAT_SEED);
+
+    assert_eq!(ss_enc_a, ss_dec_a, "encaps/decaps must agree on the shared secret");
+    assert_eq!(pk_a, pk_b, "same seed through the rng path must yield the same public key");
+    assert_eq!(ct_a, ct_b, "same seed through the rng path must yield the same ciphertext");
+    assert_eq!(ss_enc_a, ss_enc_b, "same seed through the rng path must yield the same shared secret");
+  }
+}
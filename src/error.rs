@@ -0,0 +1,17 @@
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KyberError {
+  /// The FO-transform re-encryption check failed during decapsulation.
+  DecodeFail
+}
+
+impl fmt::Display for KyberError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      KyberError::DecodeFail => write!(f, "ciphertext failed re-encryption check")
+    }
+  }
+}
+
+impl std::error::Error for KyberError {}
@@ -0,0 +1,132 @@
+//! Hybrid X25519+Kyber key encapsulation, matching the `X25519Kyber768Draft00`
+//! style of post-quantum/classical agreement being deployed in TLS 1.3.
+//!
+//! The hybrid construction concatenates a Kyber keypair with an X25519
+//! keypair and derives the final shared secret from both DH/KEM outputs plus
+//! the full transcript, so that the scheme remains secure as long as either
+//! primitive does. The wire format and KDF input ordering are fixed:
+//!
+//!   combined ciphertext  = kyber_ct || x25519_ephemeral_pub
+//!   shared secret        = KDF(kyber_ss || x25519_dh || kyber_ct || x25519_ephemeral_pub)
+//!
+//! Changing this ordering is a wire-format break, so treat it as frozen.
+
+use crate::{
+  api::{crypto_kem_keypair, crypto_kem_enc, crypto_kem_dec},
+  params::*,
+  symmetric::kdf,
+  error::KyberError
+};
+use rand_core::*;
+use x25519_dalek::{StaticSecret, PublicKey};
+
+pub const HYBRID_PUBLICKEYBYTES: usize = KYBER_PUBLICKEYBYTES + 32;
+pub const HYBRID_SECRETKEYBYTES: usize = KYBER_SECRETKEYBYTES + 32;
+pub const HYBRID_CIPHERTEXTBYTES: usize = KYBER_CIPHERTEXTBYTES + 32;
+pub const HYBRID_SSBYTES: usize = KYBER_SYMBYTES;
+
+// Name:        hybrid_keypair
+//
+// Description: Generates a combined Kyber + X25519 keypair for the hybrid
+//              KEM. The Kyber half occupies the first KYBER_PUBLICKEYBYTES/
+//              KYBER_SECRETKEYBYTES of each buffer, the X25519 half the
+//              trailing 32 bytes.
+//
+// Arguments:   - pk: output public key (HYBRID_PUBLICKEYBYTES bytes)
+//              - sk: output secret key (HYBRID_SECRETKEYBYTES bytes)
+pub fn hybrid_keypair<R>(pk: &mut[u8], sk: &mut[u8], rng: &mut R) -> Result<(), KyberError>
+  where R: RngCore + CryptoRng
+{
+  crypto_kem_keypair(&mut pk[..KYBER_PUBLICKEYBYTES], &mut sk[..KYBER_SECRETKEYBYTES], rng, None)?;
+
+  let x25519_sk = StaticSecret::random_from_rng(&mut *rng);
+  let x25519_pk = PublicKey::from(&x25519_sk);
+  pk[KYBER_PUBLICKEYBYTES..].copy_from_slice(x25519_pk.as_bytes());
+  sk[KYBER_SECRETKEYBYTES..].copy_from_slice(&x25519_sk.to_bytes());
+  Ok(())
+}
+
+// Name:        hybrid_encapsulate
+//
+// Description: Encapsulates against a hybrid public key, running the Kyber
+//              KEM against the Kyber half and an X25519 Diffie-Hellman
+//              against the X25519 half with a fresh ephemeral keypair.
+//
+// Arguments:   - ct: output combined ciphertext (HYBRID_CIPHERTEXTBYTES bytes)
+//              - ss: output shared secret (HYBRID_SSBYTES bytes)
+//              - pk: input hybrid public key (HYBRID_PUBLICKEYBYTES bytes)
+pub fn hybrid_encapsulate<R>(ct: &mut[u8], ss: &mut[u8], pk: &[u8], rng: &mut R) -> Result<(), KyberError>
+  where R: RngCore + CryptoRng
+{
+  let mut kyber_ss = [0u8; KYBER_SYMBYTES];
+  crypto_kem_enc(&mut ct[..KYBER_CIPHERTEXTBYTES], &mut kyber_ss, &pk[..KYBER_PUBLICKEYBYTES], rng, None)?;
+
+  let eph_sk = StaticSecret::random_from_rng(&mut *rng);
+  let eph_pk = PublicKey::from(&eph_sk);
+  ct[KYBER_CIPHERTEXTBYTES..].copy_from_slice(eph_pk.as_bytes());
+
+  let mut peer_x25519 = [0u8; 32];
+  peer_x25519.copy_from_slice(&pk[KYBER_PUBLICKEYBYTES..]);
+  let x25519_dh = eph_sk.diffie_hellman(&PublicKey::from(peer_x25519));
+
+  derive_hybrid_secret(ss, &kyber_ss, x25519_dh.as_bytes(), ct);
+  Ok(())
+}
+
+// Name:        hybrid_decapsulate
+//
+// Description: Mirrors hybrid_encapsulate: decapsulates the Kyber half and
+//              recomputes the X25519 Diffie-Hellman against the ephemeral
+//              public key carried in the ciphertext.
+//
+// Arguments:   - ss: output shared secret (HYBRID_SSBYTES bytes)
+//              - ct: input combined ciphertext (HYBRID_CIPHERTEXTBYTES bytes)
+//              - sk: input hybrid secret key (HYBRID_SECRETKEYBYTES bytes)
+pub fn hybrid_decapsulate(ss: &mut[u8], ct: &[u8], sk: &[u8]) -> Result<(), KyberError> {
+  let mut kyber_ss = [0u8; KYBER_SYMBYTES];
+  crypto_kem_dec(&mut kyber_ss, &ct[..KYBER_CIPHERTEXTBYTES], &sk[..KYBER_SECRETKEYBYTES])?;
+
+  let mut x25519_sk_bytes = [0u8; 32];
+  x25519_sk_bytes.copy_from_slice(&sk[KYBER_SECRETKEYBYTES..]);
+  let x25519_sk = StaticSecret::from(x25519_sk_bytes);
+
+  let mut eph_pk = [0u8; 32];
+  eph_pk.copy_from_slice(&ct[KYBER_CIPHERTEXTBYTES..]);
+  let x25519_dh = x25519_sk.diffie_hellman(&PublicKey::from(eph_pk));
+
+  derive_hybrid_secret(ss, &kyber_ss, x25519_dh.as_bytes(), ct);
+  Ok(())
+}
+
+// Binds the Kyber shared secret to the X25519 DH output and the full
+// transcript (kyber_ct || x25519_ephemeral_pub), so the hybrid secret is
+// only as weak as the *stronger* of the two primitives.
+fn derive_hybrid_secret(ss: &mut[u8], kyber_ss: &[u8], x25519_dh: &[u8], transcript: &[u8]) {
+  let mut ikm = Vec::with_capacity(kyber_ss.len() + x25519_dh.len() + transcript.len());
+  ikm.extend_from_slice(kyber_ss);
+  ikm.extend_from_slice(x25519_dh);
+  ikm.extend_from_slice(transcript);
+  kdf(ss, &ikm, ikm.len() as u64);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand_core::OsRng;
+
+  #[test]
+  fn round_trip_agrees_on_shared_secret() {
+    let mut pk = [0u8; HYBRID_PUBLICKEYBYTES];
+    let mut sk = [0u8; HYBRID_SECRETKEYBYTES];
+    hybrid_keypair(&mut pk, &mut sk, &mut OsRng).unwrap();
+
+    let mut ct = [0u8; HYBRID_CIPHERTEXTBYTES];
+    let mut ss_a = [0u8; HYBRID_SSBYTES];
+    hybrid_encapsulate(&mut ct, &mut ss_a, &pk, &mut OsRng).unwrap();
+
+    let mut ss_b = [0u8; HYBRID_SSBYTES];
+    hybrid_decapsulate(&mut ss_b, &ct, &sk).unwrap();
+
+    assert_eq!(ss_a, ss_b);
+  }
+}
@@ -0,0 +1,219 @@
+//! Authenticated key exchange on top of the KEM functions in `api`.
+//!
+//! `Uake` is the unilaterally-authenticated handshake: only the responder
+//! holds a static keypair, and the session key is only as good as the
+//! responder's authenticity. `Ake` adds a second static-key encapsulation
+//! from responder back to initiator, so both sides authenticate each other.
+//! Both call only `crypto_kem_keypair`/`crypto_kem_enc`/`crypto_kem_dec`;
+//! there is no new cryptographic primitive here, just a transcript of KEM
+//! calls whose outputs are bound together with `kdf`.
+
+use crate::{
+  api::{crypto_kem_keypair, crypto_kem_enc, crypto_kem_dec},
+  params::*,
+  symmetric::kdf,
+  error::KyberError
+};
+use rand_core::*;
+
+pub const KEX_UAKE_SENDABYTES: usize = KYBER_PUBLICKEYBYTES + KYBER_CIPHERTEXTBYTES;
+pub const KEX_UAKE_SENDBBYTES: usize = KYBER_CIPHERTEXTBYTES;
+
+pub const KEX_AKE_SENDABYTES: usize = KYBER_PUBLICKEYBYTES + KYBER_CIPHERTEXTBYTES;
+pub const KEX_AKE_SENDBBYTES: usize = 2 * KYBER_CIPHERTEXTBYTES;
+
+// Derives the session key as a KDF over the concatenation of every
+// encapsulated secret, in handshake order.
+fn derive_kex_secret(shared_secret: &mut[u8], secrets: &[&[u8]]) {
+  let mut ikm = Vec::with_capacity(secrets.iter().map(|s| s.len()).sum());
+  for s in secrets {
+    ikm.extend_from_slice(s);
+  }
+  kdf(shared_secret, &ikm, ikm.len() as u64);
+}
+
+/// Unilaterally-authenticated key exchange: the responder is authenticated
+/// by their static Kyber keypair, the initiator is not.
+pub struct Uake {
+  pub send_a: [u8; KEX_UAKE_SENDABYTES],
+  pub send_b: [u8; KEX_UAKE_SENDBBYTES],
+  pub shared_secret: [u8; KYBER_SYMBYTES],
+  eska: [u8; KYBER_SECRETKEYBYTES],
+  tk: [u8; KYBER_SYMBYTES]
+}
+
+impl Default for Uake {
+  fn default() -> Self {
+    Uake {
+      send_a: [0u8; KEX_UAKE_SENDABYTES],
+      send_b: [0u8; KEX_UAKE_SENDBBYTES],
+      shared_secret: [0u8; KYBER_SYMBYTES],
+      eska: [0u8; KYBER_SECRETKEYBYTES],
+      tk: [0u8; KYBER_SYMBYTES]
+    }
+  }
+}
+
+impl Uake {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Initiator: generates an ephemeral keypair and encapsulates to the
+  // responder's static public key. Fills self.send_a with
+  // (ephemeral_pk || ct) and stashes the ephemeral secret key plus the
+  // encapsulated secret for client_confirm.
+  pub fn client_init<R>(&mut self, pk_responder: &[u8], rng: &mut R) -> Result<(), KyberError>
+    where R: RngCore + CryptoRng
+  {
+    let mut epk = [0u8; KYBER_PUBLICKEYBYTES];
+    crypto_kem_keypair(&mut epk, &mut self.eska, rng, None)?;
+    self.send_a[..KYBER_PUBLICKEYBYTES].copy_from_slice(&epk);
+    crypto_kem_enc(&mut self.send_a[KYBER_PUBLICKEYBYTES..], &mut self.tk, pk_responder, rng, None)
+  }
+
+  // Responder: decapsulates the initiator's ciphertext with the static
+  // secret key, then encapsulates back to the initiator's ephemeral public
+  // key. Fills self.send_b and derives the final session key.
+  pub fn server_receive<R>(&mut self, send_a: &[u8], sk_responder: &[u8], rng: &mut R) -> Result<(), KyberError>
+    where R: RngCore + CryptoRng
+  {
+    let mut k1 = [0u8; KYBER_SYMBYTES];
+    let mut k2 = [0u8; KYBER_SYMBYTES];
+    let epk = &send_a[..KYBER_PUBLICKEYBYTES];
+    let ct = &send_a[KYBER_PUBLICKEYBYTES..];
+
+    crypto_kem_dec(&mut k1, ct, sk_responder)?;
+    crypto_kem_enc(&mut self.send_b, &mut k2, epk, rng, None)?;
+    derive_kex_secret(&mut self.shared_secret, &[&k1, &k2]);
+    Ok(())
+  }
+
+  // Initiator: decapsulates the responder's reply with the stashed
+  // ephemeral secret key and derives the same session key as the responder.
+  pub fn client_confirm(&mut self, send_b: &[u8]) -> Result<(), KyberError> {
+    let mut k2 = [0u8; KYBER_SYMBYTES];
+    crypto_kem_dec(&mut k2, send_b, &self.eska)?;
+    derive_kex_secret(&mut self.shared_secret, &[&self.tk, &k2]);
+    Ok(())
+  }
+}
+
+/// Mutually-authenticated key exchange: both initiator and responder hold
+/// static Kyber keypairs and authenticate each other.
+pub struct Ake {
+  pub send_a: [u8; KEX_AKE_SENDABYTES],
+  pub send_b: [u8; KEX_AKE_SENDBBYTES],
+  pub shared_secret: [u8; KYBER_SYMBYTES],
+  eska: [u8; KYBER_SECRETKEYBYTES],
+  tk: [u8; KYBER_SYMBYTES]
+}
+
+impl Default for Ake {
+  fn default() -> Self {
+    Ake {
+      send_a: [0u8; KEX_AKE_SENDABYTES],
+      send_b: [0u8; KEX_AKE_SENDBBYTES],
+      shared_secret: [0u8; KYBER_SYMBYTES],
+      eska: [0u8; KYBER_SECRETKEYBYTES],
+      tk: [0u8; KYBER_SYMBYTES]
+    }
+  }
+}
+
+impl Ake {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Initiator: identical to Uake::client_init -- ephemeral keypair,
+  // encapsulate to the responder's static public key.
+  pub fn client_init<R>(&mut self, pk_responder: &[u8], rng: &mut R) -> Result<(), KyberError>
+    where R: RngCore + CryptoRng
+  {
+    let mut epk = [0u8; KYBER_PUBLICKEYBYTES];
+    crypto_kem_keypair(&mut epk, &mut self.eska, rng, None)?;
+    self.send_a[..KYBER_PUBLICKEYBYTES].copy_from_slice(&epk);
+    crypto_kem_enc(&mut self.send_a[KYBER_PUBLICKEYBYTES..], &mut self.tk, pk_responder, rng, None)
+  }
+
+  // Responder: decapsulates the initiator's ciphertext, encapsulates back
+  // to the initiator's ephemeral public key, AND encapsulates a second
+  // secret to the initiator's static public key, authenticating the
+  // initiator in turn. Fills self.send_b = (ct_to_ephemeral || ct_to_static).
+  pub fn server_receive<R>(
+    &mut self,
+    send_a: &[u8],
+    sk_responder: &[u8],
+    pk_initiator: &[u8],
+    rng: &mut R
+  ) -> Result<(), KyberError>
+    where R: RngCore + CryptoRng
+  {
+    let mut k1 = [0u8; KYBER_SYMBYTES];
+    let mut k2 = [0u8; KYBER_SYMBYTES];
+    let mut k3 = [0u8; KYBER_SYMBYTES];
+    let epk = &send_a[..KYBER_PUBLICKEYBYTES];
+    let ct = &send_a[KYBER_PUBLICKEYBYTES..];
+
+    crypto_kem_dec(&mut k1, ct, sk_responder)?;
+    crypto_kem_enc(&mut self.send_b[..KYBER_CIPHERTEXTBYTES], &mut k2, epk, rng, None)?;
+    crypto_kem_enc(&mut self.send_b[KYBER_CIPHERTEXTBYTES..], &mut k3, pk_initiator, rng, None)?;
+    derive_kex_secret(&mut self.shared_secret, &[&k1, &k2, &k3]);
+    Ok(())
+  }
+
+  // Initiator: decapsulates both of the responder's ciphertexts -- one
+  // with the stashed ephemeral secret key, one with the initiator's own
+  // static secret key -- and derives the same session key as the responder.
+  pub fn client_confirm(&mut self, send_b: &[u8], sk_initiator: &[u8]) -> Result<(), KyberError> {
+    let mut k2 = [0u8; KYBER_SYMBYTES];
+    let mut k3 = [0u8; KYBER_SYMBYTES];
+    crypto_kem_dec(&mut k2, &send_b[..KYBER_CIPHERTEXTBYTES], &self.eska)?;
+    crypto_kem_dec(&mut k3, &send_b[KYBER_CIPHERTEXTBYTES..], sk_initiator)?;
+    derive_kex_secret(&mut self.shared_secret, &[&self.tk, &k2, &k3]);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand_core::OsRng;
+
+  #[test]
+  fn uake_round_trip_agrees_on_shared_secret() {
+    let mut pk_responder = [0u8; KYBER_PUBLICKEYBYTES];
+    let mut sk_responder = [0u8; KYBER_SECRETKEYBYTES];
+    crypto_kem_keypair(&mut pk_responder, &mut sk_responder, &mut OsRng, None).unwrap();
+
+    let mut client = Uake::new();
+    let mut server = Uake::new();
+
+    client.client_init(&pk_responder, &mut OsRng).unwrap();
+    server.server_receive(&client.send_a, &sk_responder, &mut OsRng).unwrap();
+    client.client_confirm(&server.send_b).unwrap();
+
+    assert_eq!(client.shared_secret, server.shared_secret);
+  }
+
+  #[test]
+  fn ake_round_trip_agrees_on_shared_secret() {
+    let mut pk_responder = [0u8; KYBER_PUBLICKEYBYTES];
+    let mut sk_responder = [0u8; KYBER_SECRETKEYBYTES];
+    crypto_kem_keypair(&mut pk_responder, &mut sk_responder, &mut OsRng, None).unwrap();
+
+    let mut pk_initiator = [0u8; KYBER_PUBLICKEYBYTES];
+    let mut sk_initiator = [0u8; KYBER_SECRETKEYBYTES];
+    crypto_kem_keypair(&mut pk_initiator, &mut sk_initiator, &mut OsRng, None).unwrap();
+
+    let mut client = Ake::new();
+    let mut server = Ake::new();
+
+    client.client_init(&pk_responder, &mut OsRng).unwrap();
+    server.server_receive(&client.send_a, &sk_responder, &pk_initiator, &mut OsRng).unwrap();
+    client.client_confirm(&server.send_b, &sk_initiator).unwrap();
+
+    assert_eq!(client.shared_secret, server.shared_secret);
+  }
+}